@@ -0,0 +1,603 @@
+//! Closure and nested-function parsing with capture detection.
+//!
+//! Walks function bodies looking for closure expressions (`|x| ...` and
+//! `move |x| ...`), recording each one as an anonymous callable nested
+//! under its enclosing named function, along with its parameter list,
+//! arity, and the set of outer variables its body references (an
+//! identifier counts as captured when it's bound by the enclosing
+//! function — a parameter or an earlier `let` — and isn't shadowed by one
+//! of the closure's own parameters). Nested named functions are recorded
+//! too, but — matching real Rust semantics — don't see their enclosing
+//! function's locals at all, so no capture analysis is run for them.
+//!
+//! A function's (and so a closure's) name is qualified by its enclosing
+//! `impl`/`trait` target, not just by enclosing `fn`s, so `new` in `impl A`
+//! and `new` in `impl B` are recorded as `A::new` and `B::new` rather than
+//! colliding.
+
+use std::collections::{BTreeSet, HashSet};
+
+use crate::util::{is_item_start, mask_line, split_visibility, ItemKind, Span};
+
+/// A nested or top-level named function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInfo {
+    /// Fully-qualified name, e.g. `outer::inner` for a function nested
+    /// inside `outer`.
+    pub name: String,
+    pub span: Span,
+    pub params: Vec<String>,
+}
+
+/// A closure expression, anonymous except for the function it's nested
+/// under.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Closure {
+    /// Fully-qualified name of the enclosing function.
+    pub owner: String,
+    pub span: Span,
+    pub params: Vec<String>,
+    pub arity: usize,
+    pub is_move: bool,
+    /// Names of outer-scope variables referenced in the closure's body,
+    /// sorted for stable comparisons.
+    pub captures: Vec<String>,
+}
+
+/// Everything this module extracts from a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ClosureInfo {
+    pub functions: Vec<FunctionInfo>,
+    pub closures: Vec<Closure>,
+}
+
+struct Scope {
+    qualified_name: String,
+    depth_at_open: usize,
+    bound: HashSet<String>,
+}
+
+/// Extracts every named function, closure, and capture set from `source`.
+pub fn extract(source: &str) -> ClosureInfo {
+    let lines: Vec<&str> = source.lines().collect();
+    let mut functions: Vec<FunctionInfo> = Vec::new();
+    let mut closures: Vec<Closure> = Vec::new();
+
+    let mut depth: usize = 0;
+    let mut scope_stack: Vec<Scope> = Vec::new();
+    let mut pending_open: Option<(String, Vec<String>)> = None;
+
+    for (idx, &raw_line) in lines.iter().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let masked = mask_line(raw_line);
+        let masked_trimmed = masked.trim();
+
+        if let Some((kind, name)) = is_item_start(masked_trimmed) {
+            match kind {
+                ItemKind::Fn => {
+                    if let Some((name, params)) = parse_fn_signature(masked_trimmed) {
+                        let qualified = match scope_stack.last() {
+                            Some(parent) => format!("{}::{name}", parent.qualified_name),
+                            None => name,
+                        };
+                        functions.push(FunctionInfo {
+                            name: qualified.clone(),
+                            span: Span::single(line_no),
+                            params: params.clone(),
+                        });
+                        pending_open = Some((qualified, params));
+                    }
+                }
+                ItemKind::Impl => {
+                    // `is_item_start` doesn't truncate the `impl` case at its
+                    // opening brace or a same-line `where` clause (unlike
+                    // struct/enum/fn/mod), so clean it up before qualifying
+                    // nested functions by it.
+                    let target = impl_scope_name(&name);
+                    let qualified = match scope_stack.last() {
+                        Some(parent) => format!("{}::{target}", parent.qualified_name),
+                        None => target,
+                    };
+                    pending_open = Some((qualified, Vec::new()));
+                }
+                ItemKind::Trait => {
+                    let qualified = match scope_stack.last() {
+                        Some(parent) => format!("{}::{name}", parent.qualified_name),
+                        None => name,
+                    };
+                    pending_open = Some((qualified, Vec::new()));
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(scope) = scope_stack.last() {
+            for found in find_closures(&lines, idx) {
+                let own_params: HashSet<&str> = found.params.iter().map(|s| s.as_str()).collect();
+                let captures: BTreeSet<String> = identifiers(&found.body)
+                    .into_iter()
+                    .filter(|id| !own_params.contains(id.as_str()) && scope.bound.contains(id.as_str()))
+                    .collect();
+                closures.push(Closure {
+                    owner: scope.qualified_name.clone(),
+                    span: Span::single(line_no),
+                    arity: found.params.len(),
+                    params: found.params,
+                    is_move: found.is_move,
+                    captures: captures.into_iter().collect(),
+                });
+            }
+        }
+
+        if let Some(name) = parse_let_binding(masked_trimmed) {
+            if let Some(scope) = scope_stack.last_mut() {
+                scope.bound.insert(name);
+            }
+        }
+
+        for ch in masked.chars() {
+            match ch {
+                '{' => {
+                    if let Some((qualified_name, params)) = pending_open.take() {
+                        scope_stack.push(Scope {
+                            qualified_name,
+                            depth_at_open: depth,
+                            bound: params.into_iter().collect(),
+                        });
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if let Some(top) = scope_stack.last() {
+                        if top.depth_at_open == depth {
+                            scope_stack.pop();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    ClosureInfo { functions, closures }
+}
+
+/// Parses a `fn name(params...)` line (skipping any leading visibility
+/// qualifier) into its name and parameter bindings.
+fn parse_fn_signature(line: &str) -> Option<(String, Vec<String>)> {
+    let (_, rest) = split_visibility(line);
+    let rest = rest.trim_start().strip_prefix("fn ")?;
+    let open = rest.find('(')?;
+    let name = rest[..open].split('<').next().unwrap_or(&rest[..open]).trim().to_string();
+    let after_open = &rest[open + 1..];
+    let close = find_matching_paren(after_open)?;
+    Some((name, parse_params(&after_open[..close])))
+}
+
+/// Reduces an `impl` block's raw, untruncated name text (e.g. `"Circle {"`,
+/// `"<T> Container<T> {"`, `"Named for Plain where Plain: Sized {"`) to the
+/// plain target type name nested functions should be qualified under.
+fn impl_scope_name(raw: &str) -> String {
+    let rest = raw.trim().trim_end_matches('{').trim();
+    let (_, rest) = split_generics(rest);
+    let rest = strip_where_clause(rest.trim());
+    let target = match rest.find(" for ") {
+        Some(pos) => &rest[pos + 5..],
+        None => rest,
+    };
+    simple_type_name(target)
+}
+
+/// Strips a leading `<...>` generic parameter list (tracking nested angle
+/// brackets), returning it alongside the remaining text. Mirrors
+/// `impls.rs`'s helper of the same name.
+fn split_generics(name: &str) -> (String, &str) {
+    let Some(rest) = name.strip_prefix('<') else {
+        return (String::new(), name);
+    };
+    let mut depth = 1i32;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (format!("<{}", &rest[..=i]), rest[i + 1..].trim_start());
+                }
+            }
+            _ => {}
+        }
+    }
+    (String::new(), name)
+}
+
+/// Strips a trailing `where ...` clause. Mirrors `impls.rs`'s helper of the
+/// same name.
+fn strip_where_clause(rest: &str) -> &str {
+    match rest.find(" where ") {
+        Some(pos) => rest[..pos].trim(),
+        None => rest,
+    }
+}
+
+/// Reduces a type reference like `"Container<T>"` to its plain name
+/// (`"Container"`). Mirrors `impls.rs`'s helper of the same name.
+fn simple_type_name(text: &str) -> String {
+    let text = text.trim();
+    let end = text.find('<').unwrap_or(text.len());
+    text[..end].trim().to_string()
+}
+
+/// Index (relative to `s`) of the `)` matching the `(` already consumed by
+/// the caller.
+fn find_matching_paren(s: &str) -> Option<usize> {
+    let mut depth = 1i32;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Splits a parameter list on top-level commas and reduces each parameter
+/// to its binding name (stripping `&`/`mut` and any type annotation).
+fn parse_params(text: &str) -> Vec<String> {
+    split_top_level(text)
+        .into_iter()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(param_name)
+        .collect()
+}
+
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn param_name(p: &str) -> String {
+    let p = p.trim();
+    if p == "self" || p == "&self" || p == "&mut self" {
+        return "self".to_string();
+    }
+    p.split(':')
+        .next()
+        .unwrap_or(p)
+        .trim()
+        .trim_start_matches('&')
+        .trim_start_matches("mut ")
+        .trim()
+        .to_string()
+}
+
+/// Recognizes a simple `let name = ...;` / `let name: Type = ...;` binding
+/// (destructuring patterns aren't handled, matching this module's
+/// line-oriented scope).
+fn parse_let_binding(line: &str) -> Option<String> {
+    let rest = line.trim_start().strip_prefix("let ")?;
+    let rest = rest.strip_prefix("mut ").unwrap_or(rest);
+    let end = rest.find([':', '=']).unwrap_or(rest.len());
+    let name = rest[..end].trim();
+    if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+struct FoundClosure {
+    params: Vec<String>,
+    is_move: bool,
+    body: String,
+}
+
+/// Scans `lines[idx]` for closure expressions (`|x| ...`, `move |x| ...`),
+/// returning each one found left to right. A block-bodied closure
+/// (`|x| { ... }`) follows its body across subsequent lines if needed,
+/// since that's the common style for anything beyond a trivial expression.
+fn find_closures(lines: &[&str], idx: usize) -> Vec<FoundClosure> {
+    let masked = mask_line(lines[idx]);
+    let chars: Vec<char> = masked.chars().collect();
+    let n = chars.len();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < n {
+        let mut is_move = false;
+        let mut pipe_start = i;
+
+        if matches_word(&chars, i, "move") {
+            let after = skip_ws(&chars, i + 4);
+            if after < n && chars[after] == '|' {
+                is_move = true;
+                pipe_start = after;
+            }
+        }
+
+        if chars.get(pipe_start) == Some(&'|') && (is_move || pipe_pair_allowed(&chars, pipe_start)) {
+            if let Some((params_end, params)) = parse_closure_params(&chars, pipe_start) {
+                let body_start = skip_ws(&chars, params_end + 1);
+                if chars.get(body_start) == Some(&'{') {
+                    let (body, closes_on_this_line) = scan_block_body(lines, idx, body_start);
+                    out.push(FoundClosure { params, is_move, body });
+                    match closes_on_this_line {
+                        Some(end_col) => {
+                            i = end_col.max(pipe_start + 1);
+                            continue;
+                        }
+                        // The close brace is on a later line, already
+                        // consumed by `scan_block_body` — nothing more to
+                        // find on this line past the closure's start.
+                        None => break,
+                    }
+                }
+                let body_end = scan_body(&chars, body_start);
+                out.push(FoundClosure {
+                    params,
+                    is_move,
+                    body: chars[body_start..body_end].iter().collect(),
+                });
+                i = body_end.max(pipe_start + 1);
+                continue;
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Scans a closure's block body (`{ ... }`, the opening brace already
+/// located at `lines[start_idx]` column `open_col`) across as many lines as
+/// needed to find the matching close, returning the raw body text (braces
+/// excluded, lines joined with `\n`) and, if the close falls on `start_idx`
+/// itself, the column just past it (so the caller can keep scanning that
+/// line for further closures).
+fn scan_block_body(lines: &[&str], start_idx: usize, open_col: usize) -> (String, Option<usize>) {
+    let mut depth = 1i32;
+    let mut body_lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut line_idx = start_idx;
+    let mut col = open_col + 1;
+
+    loop {
+        let Some(raw_line) = lines.get(line_idx) else {
+            body_lines.push(current);
+            return (body_lines.join("\n").trim().to_string(), None);
+        };
+        let raw: Vec<char> = raw_line.chars().collect();
+        let masked: Vec<char> = mask_line(raw_line).chars().collect();
+        while col < masked.len() {
+            match masked[col] {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        body_lines.push(current);
+                        let text = body_lines.join("\n").trim().to_string();
+                        let end_col = col + 1;
+                        return (text, (line_idx == start_idx).then_some(end_col));
+                    }
+                }
+                _ => {}
+            }
+            current.push(raw[col]);
+            col += 1;
+        }
+        body_lines.push(std::mem::take(&mut current));
+        line_idx += 1;
+        col = 0;
+    }
+}
+
+/// Whether `word` occurs at `i` as a whole identifier (not a substring of a
+/// longer one).
+fn matches_word(chars: &[char], i: usize, word: &str) -> bool {
+    if i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '_') {
+        return false;
+    }
+    let wc: Vec<char> = word.chars().collect();
+    if i + wc.len() > chars.len() || chars[i..i + wc.len()] != wc[..] {
+        return false;
+    }
+    let after = i + wc.len();
+    after == chars.len() || !(chars[after].is_alphanumeric() || chars[after] == '_')
+}
+
+fn skip_ws(chars: &[char], mut i: usize) -> usize {
+    while i < chars.len() && chars[i].is_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// Whether the `|` at `i` plausibly opens a closure's parameter list rather
+/// than being a bitwise-or: true if the previous non-whitespace character
+/// is one that can precede a closure expression (or there isn't one).
+fn pipe_pair_allowed(chars: &[char], i: usize) -> bool {
+    let mut k = i;
+    while k > 0 {
+        k -= 1;
+        if chars[k].is_whitespace() {
+            continue;
+        }
+        return matches!(chars[k], '(' | ',' | '=');
+    }
+    true
+}
+
+/// Parses a closure's `|params|` (the opening `|` already located at
+/// `start`), returning the index of the closing `|` and the parsed params.
+fn parse_closure_params(chars: &[char], start: usize) -> Option<(usize, Vec<String>)> {
+    if chars.get(start + 1) == Some(&'|') {
+        return Some((start + 1, Vec::new()));
+    }
+    let mut depth = 0i32;
+    let mut j = start + 1;
+    while j < chars.len() {
+        match chars[j] {
+            '|' if depth == 0 => {
+                let text: String = chars[start + 1..j].iter().collect();
+                return Some((j, parse_params(&text)));
+            }
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth -= 1,
+            ';' | '{' | '}' => return None,
+            _ => {}
+        }
+        j += 1;
+    }
+    None
+}
+
+/// Scans an expression-style closure body starting at `start`, stopping at
+/// the first comma/semicolon at the body's own nesting depth, or at a
+/// closing bracket that belongs to an enclosing call rather than the body
+/// itself. Returns the index of that stopping character (or the line's
+/// length if none is found).
+fn scan_body(chars: &[char], start: usize) -> usize {
+    let mut depth = 0i32;
+    let mut j = start;
+    while j < chars.len() {
+        match chars[j] {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => {
+                if depth == 0 {
+                    return j;
+                }
+                depth -= 1;
+            }
+            ',' | ';' if depth == 0 => return j,
+            _ => {}
+        }
+        j += 1;
+    }
+    j
+}
+
+/// Extracts maximal identifier-like runs (`[A-Za-z_][A-Za-z0-9_]*`) from
+/// `text`, in order, including repeats.
+fn identifiers(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_alphabetic() || chars[i] == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            out.push(chars[start..i].iter().collect());
+        } else {
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/closures.rs");
+
+    fn closures_in<'a>(info: &'a ClosureInfo, owner: &str) -> Vec<&'a Closure> {
+        info.closures.iter().filter(|c| c.owner == owner).collect()
+    }
+
+    #[test]
+    fn closure_with_no_captures_has_empty_capture_list() {
+        let info = extract(FIXTURE);
+        let closure = closures_in(&info, "increment_all")[0];
+        assert_eq!(closure.params, vec!["n"]);
+        assert_eq!(closure.arity, 1);
+        assert!(closure.captures.is_empty());
+    }
+
+    #[test]
+    fn closure_captures_outer_parameter_by_name() {
+        let info = extract(FIXTURE);
+        let closure = closures_in(&info, "add_offset")[0];
+        assert_eq!(closure.captures, vec!["offset"]);
+    }
+
+    #[test]
+    fn closure_capture_excludes_locally_bound_fold_accumulator() {
+        let info = extract(FIXTURE);
+        let closure = closures_in(&info, "scaled_sum")[0];
+        assert_eq!(closure.params, vec!["acc", "n"]);
+        assert_eq!(closure.captures, vec!["scale"]);
+    }
+
+    #[test]
+    fn move_closure_is_marked_and_captures_by_value() {
+        let info = extract(FIXTURE);
+        let closure = closures_in(&info, "make_greeter")[0];
+        assert!(closure.is_move);
+        assert_eq!(closure.arity, 0);
+        assert_eq!(closure.captures, vec!["name"]);
+    }
+
+    #[test]
+    fn nested_named_function_is_recorded_without_capture_analysis() {
+        let info = extract(FIXTURE);
+        let inner = info.functions.iter().find(|f| f.name == "outer::inner").unwrap();
+        assert_eq!(inner.params, vec!["x"]);
+        assert!(closures_in(&info, "outer").is_empty());
+    }
+
+    #[test]
+    fn closure_with_block_body_spanning_multiple_lines_still_captures() {
+        let info = extract(FIXTURE);
+        let closure = closures_in(&info, "scale_all")[0];
+        assert_eq!(closure.params, vec!["n"]);
+        assert_eq!(closure.captures, vec!["limit"]);
+    }
+
+    #[test]
+    fn same_named_methods_in_different_impls_are_qualified_distinctly() {
+        let info = extract(FIXTURE);
+        assert!(info.functions.iter().any(|f| f.name == "Adder::new"));
+        assert!(info.functions.iter().any(|f| f.name == "Multiplier::new"));
+        assert!(!info.functions.iter().any(|f| f.name == "new"));
+
+        assert_eq!(closures_in(&info, "Adder::make").len(), 1);
+        assert_eq!(closures_in(&info, "Multiplier::make").len(), 1);
+    }
+
+    #[test]
+    fn closure_passed_as_callback_captures_earlier_let_binding() {
+        let info = extract(FIXTURE);
+        let closure = closures_in(&info, "run_pipeline")
+            .into_iter()
+            .find(|c| c.params == vec!["x"])
+            .unwrap();
+        assert_eq!(closure.captures, vec!["total"]);
+    }
+}
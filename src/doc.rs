@@ -0,0 +1,408 @@
+//! Documentation and doctest extraction.
+//!
+//! Walks a source file and attaches outer (`///`) and inner (`//!`) comment
+//! text to the item it documents, then scans that text for fenced Rust (or
+//! unlabeled) code blocks and emits each one as a [`Doctest`] record; fences
+//! labeled with another language are skipped. An unterminated fence
+//! is treated as extending to the end of the comment, and the legacy
+//! `~~~ {.rust}` fence style is normalized to the same block type as a
+//! backtick fence so older sources parse identically.
+
+use std::collections::HashMap;
+
+use crate::util::{is_item_start, mask_line, Span};
+
+/// A fenced code block found inside a doc comment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Doctest {
+    /// Fully-qualified name of the item whose doc comment contains this block.
+    pub owner: String,
+    /// Line span of the fence, including its opening/closing markers.
+    pub span: Span,
+    /// Raw, unindented body of the code block, ready to be compiled/run.
+    pub body: String,
+}
+
+/// An item with its associated documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentedItem {
+    pub name: String,
+    pub kind: String,
+    pub span: Span,
+    pub outer_doc: Option<String>,
+    pub inner_doc: Option<String>,
+    pub doctests: Vec<Doctest>,
+}
+
+struct ScopeFrame {
+    qualified_name: String,
+    depth_at_open: usize,
+}
+
+/// Whether items of this kind may carry an inner (`//!`) doc comment as the
+/// first thing in their body (struct/enum field lists cannot: rustc rejects
+/// `//!` there with E0753).
+fn allows_inner_doc(kind: &str) -> bool {
+    matches!(kind, "fn" | "trait" | "impl" | "mod" | "crate")
+}
+
+/// Extracts every documented item and doctest from `source`.
+pub fn extract(source: &str) -> Vec<DocumentedItem> {
+    let mut results: Vec<DocumentedItem> = Vec::new();
+    let mut index_of: HashMap<String, usize> = HashMap::new();
+
+    // Synthetic root item so a file-level `//!` block has somewhere to go.
+    results.push(DocumentedItem {
+        name: "crate".to_string(),
+        kind: "crate".to_string(),
+        span: Span::single(1),
+        outer_doc: None,
+        inner_doc: None,
+        doctests: Vec::new(),
+    });
+    index_of.insert("crate".to_string(), 0);
+
+    let mut depth: usize = 0;
+    let mut scope_stack: Vec<ScopeFrame> = Vec::new();
+    let mut pending_open: Option<(String, &'static str)> = None;
+
+    let mut pending_outer: Vec<(usize, String)> = Vec::new();
+    let mut awaiting_inner: Option<String> = Some("crate".to_string());
+    let mut inner_block: Vec<(usize, String)> = Vec::new();
+
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = raw_line.trim();
+
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        if let Some(content) = trimmed.strip_prefix("///") {
+            finalize_inner_if_pending(&mut awaiting_inner, &mut inner_block, &mut results, &index_of);
+            pending_outer.push((line_no, strip_one_space(content)));
+            continue;
+        }
+
+        if let Some(content) = trimmed.strip_prefix("//!") {
+            if awaiting_inner.is_some() {
+                inner_block.push((line_no, strip_one_space(content)));
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("//") {
+            // A plain comment breaks adjacency between a doc block and the
+            // item it would otherwise document.
+            pending_outer.clear();
+            continue;
+        }
+
+        if trimmed.starts_with("#[") && trimmed.ends_with(']') {
+            // An attribute (`#[derive(..)]`, `#[cfg(..)]`, ...) sits between
+            // a doc comment and the item it decorates just as often as the
+            // item itself does, so it shouldn't break that adjacency either.
+            continue;
+        }
+
+        finalize_inner_if_pending(&mut awaiting_inner, &mut inner_block, &mut results, &index_of);
+
+        let masked = mask_line(raw_line);
+        let masked_trimmed = masked.trim();
+
+        if let Some((kind, name)) = is_item_start(masked_trimmed) {
+            let enclosing = scope_stack.last().map(|f| f.qualified_name.as_str());
+            let qualified = qualify(enclosing, &name);
+            let (outer_doc, mut doctests) = finalize_outer(&qualified, &mut pending_outer);
+            let item_kind = kind.as_str();
+
+            results.push(DocumentedItem {
+                name: qualified.clone(),
+                kind: item_kind.to_string(),
+                span: Span::single(line_no),
+                outer_doc,
+                inner_doc: None,
+                doctests: std::mem::take(&mut doctests),
+            });
+            index_of.insert(qualified.clone(), results.len() - 1);
+            pending_open = Some((qualified, item_kind));
+        } else {
+            pending_outer.clear();
+        }
+
+        // Track brace depth (and forward declarations ending in `;`) to
+        // know when this item's body opens/closes and to qualify nested
+        // item names (e.g. `Describe::describe`).
+        for ch in masked.chars() {
+            match ch {
+                '{' => {
+                    if let Some((qualified_name, kind)) = pending_open.take() {
+                        let depth_at_open = depth;
+                        if allows_inner_doc(kind) {
+                            awaiting_inner = Some(qualified_name.clone());
+                            inner_block.clear();
+                        }
+                        scope_stack.push(ScopeFrame {
+                            qualified_name,
+                            depth_at_open,
+                        });
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if let Some(top) = scope_stack.last() {
+                        if top.depth_at_open == depth {
+                            let frame = scope_stack.pop().unwrap();
+                            if awaiting_inner.as_deref() == Some(frame.qualified_name.as_str()) {
+                                finalize_inner_if_pending(
+                                    &mut awaiting_inner,
+                                    &mut inner_block,
+                                    &mut results,
+                                    &index_of,
+                                );
+                            }
+                        }
+                    }
+                }
+                ';' => {
+                    pending_open = None;
+                }
+                _ => {}
+            }
+        }
+    }
+    finalize_inner_if_pending(&mut awaiting_inner, &mut inner_block, &mut results, &index_of);
+
+    // Drop the synthetic crate item if the file never attached anything to it.
+    if results[0].inner_doc.is_none() && results[0].doctests.is_empty() {
+        results.remove(0);
+    }
+    results
+}
+
+fn qualify(enclosing: Option<&str>, name: &str) -> String {
+    match enclosing {
+        Some(parent) if !parent.is_empty() && parent != "crate" => {
+            format!("{}::{}", simple_target(parent), name)
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// Reduces an `impl` target like `"Animal for Dog"` or `"Container<T>"` to
+/// the plain type name (`"Dog"`, `"Container"`) used to qualify nested items.
+fn simple_target(text: &str) -> String {
+    // `is_item_start` leaves an `impl` line untruncated, so drop its opening
+    // brace, a leading `<...>` generic list, and a same-line `where` clause.
+    let text = text.trim().trim_end_matches('{').trim();
+    let text = if text.starts_with('<') {
+        let mut depth = 0i32;
+        let end = text
+            .char_indices()
+            .find(|&(_, c)| {
+                depth += match c {
+                    '<' => 1,
+                    '>' => -1,
+                    _ => 0,
+                };
+                depth == 0
+            })
+            .map_or(0, |(i, _)| i + 1);
+        text[end..].trim_start()
+    } else {
+        text
+    };
+    let text = text.find(" where ").map_or(text, |pos| &text[..pos]);
+    let target = match text.find(" for ") {
+        Some(pos) => &text[pos + 5..],
+        None => text,
+    };
+    let end = target.find('<').unwrap_or(target.len());
+    target[..end].trim().to_string()
+}
+
+fn strip_one_space(s: &str) -> String {
+    s.strip_prefix(' ').unwrap_or(s).to_string()
+}
+
+fn finalize_outer(owner: &str, pending: &mut Vec<(usize, String)>) -> (Option<String>, Vec<Doctest>) {
+    if pending.is_empty() {
+        return (None, Vec::new());
+    }
+    let lines = std::mem::take(pending);
+    let doctests = scan_doctests(owner, &lines);
+    let text = lines.into_iter().map(|(_, t)| t).collect::<Vec<_>>().join("\n");
+    (Some(text), doctests)
+}
+
+fn finalize_inner_if_pending(
+    awaiting_inner: &mut Option<String>,
+    inner_block: &mut Vec<(usize, String)>,
+    results: &mut [DocumentedItem],
+    index_of: &HashMap<String, usize>,
+) {
+    if let Some(owner) = awaiting_inner.take() {
+        if !inner_block.is_empty() {
+            let doctests = scan_doctests(&owner, inner_block);
+            let text = inner_block
+                .iter()
+                .map(|(_, t)| t.clone())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if let Some(&i) = index_of.get(&owner) {
+                results[i].inner_doc = Some(text);
+                results[i].doctests.extend(doctests);
+            }
+        }
+    }
+    inner_block.clear();
+}
+
+/// Scans already-doc-marker-stripped lines for fenced code blocks,
+/// normalizing the legacy `~~~ {.rust}` style to the same record shape as a
+/// backtick fence. An unterminated fence runs to the end of `lines`.
+fn scan_doctests(owner: &str, lines: &[(usize, String)]) -> Vec<Doctest> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum Marker {
+        Backtick,
+        Tilde,
+    }
+
+    let mut out = Vec::new();
+    let mut open: Option<(Marker, usize, Vec<String>)> = None;
+
+    for (line_no, text) in lines {
+        let t = text.trim();
+        match &mut open {
+            None => {
+                if is_rust_fence_open(t, "```") {
+                    open = Some((Marker::Backtick, *line_no, Vec::new()));
+                } else if is_rust_fence_open(t, "~~~") {
+                    open = Some((Marker::Tilde, *line_no, Vec::new()));
+                }
+            }
+            Some((marker, start, body)) => {
+                let closes = matches!(
+                    (&marker, t),
+                    (Marker::Backtick, "```") | (Marker::Tilde, "~~~")
+                );
+                if closes {
+                    out.push(Doctest {
+                        owner: owner.to_string(),
+                        span: Span {
+                            start_line: *start,
+                            end_line: *line_no,
+                        },
+                        body: body.join("\n"),
+                    });
+                    open = None;
+                } else {
+                    body.push(text.clone());
+                }
+            }
+        }
+    }
+    // Unterminated fence: extends to the end of the comment.
+    if let Some((_, start, body)) = open {
+        let end_line = lines.last().map(|(n, _)| *n).unwrap_or(start);
+        out.push(Doctest {
+            owner: owner.to_string(),
+            span: Span {
+                start_line: start,
+                end_line,
+            },
+            body: body.join("\n"),
+        });
+    }
+    out
+}
+
+/// Whether a trimmed line opens a rust (or unlabeled, which defaults to
+/// rust per rustdoc convention) fence for the given marker (`` ``` `` or `~~~`).
+fn is_rust_fence_open(trimmed: &str, marker: &str) -> bool {
+    if trimmed == marker {
+        return true;
+    }
+    match trimmed.strip_prefix(marker) {
+        Some(rest) => {
+            let rest = rest.trim();
+            rest.is_empty() || rest == "rust" || rest == "{.rust}"
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/doc_comments.rs");
+
+    fn find<'a>(items: &'a [DocumentedItem], name: &str) -> &'a DocumentedItem {
+        items
+            .iter()
+            .find(|i| i.name == name)
+            .unwrap_or_else(|| panic!("no item named {name}, have {:?}", items.iter().map(|i| &i.name).collect::<Vec<_>>()))
+    }
+
+    #[test]
+    fn attaches_outer_doc_and_single_doctest() {
+        let items = extract(FIXTURE);
+        let add_one = find(&items, "add_one");
+        assert!(add_one.outer_doc.as_ref().unwrap().contains("Adds one"));
+        assert_eq!(add_one.doctests.len(), 1);
+        assert!(add_one.doctests[0].body.contains("assert_eq!(result, 5)"));
+    }
+
+    #[test]
+    fn multiple_doctests_in_one_doc_comment() {
+        let items = extract(FIXTURE);
+        let square = find(&items, "square");
+        assert_eq!(square.doctests.len(), 2);
+        assert!(square.doctests[0].body.contains("square(2), 4"));
+        assert!(square.doctests[1].body.contains("square(-3), 9"));
+    }
+
+    #[test]
+    fn normalizes_legacy_tilde_fence() {
+        let items = extract(FIXTURE);
+        let subtract = find(&items, "subtract");
+        assert_eq!(subtract.doctests.len(), 1);
+        assert!(subtract.doctests[0].body.contains("subtract(5, 3), 2"));
+    }
+
+    #[test]
+    fn unterminated_fence_runs_to_end_of_comment() {
+        let items = extract(FIXTURE);
+        let divide = find(&items, "divide");
+        assert_eq!(divide.doctests.len(), 1);
+        assert!(divide.doctests[0].body.contains("divide(10, 2), 5"));
+    }
+
+    #[test]
+    fn inner_doc_attaches_to_enclosing_module() {
+        let items = extract(FIXTURE);
+        let coordinates = find(&items, "coordinates");
+        assert_eq!(coordinates.kind, "mod");
+        assert!(coordinates
+            .inner_doc
+            .as_ref()
+            .unwrap()
+            .contains("Coordinate helpers"));
+    }
+
+    #[test]
+    fn outer_doc_survives_an_intervening_attribute() {
+        let items = extract(FIXTURE);
+        let user = find(&items, "User");
+        assert!(user.outer_doc.as_ref().unwrap().contains("A registered user"));
+    }
+
+    #[test]
+    fn nested_trait_method_doc_is_qualified() {
+        let items = extract(FIXTURE);
+        let describe = find(&items, "Describe::describe");
+        assert_eq!(describe.doctests.len(), 1);
+    }
+}
@@ -0,0 +1,308 @@
+//! Impl/trait linkage graph.
+//!
+//! Links every inherent and trait `impl` block to the struct/enum it
+//! targets, and trait impls additionally to the trait they implement, so
+//! downstream tooling can ask "what traits does `Dog` implement" and "which
+//! of a trait impl's methods are explicit vs. inherited from the trait's
+//! default". Each impl's generic parameters and lifetimes are carried
+//! through as the raw text between `impl` and the target type.
+
+use crate::util::{is_item_start, mask_line, ItemKind, Span};
+
+/// A method declared inside a `trait` body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitMethod {
+    pub name: String,
+    pub span: Span,
+    /// Whether the trait provides a default body for this method.
+    pub has_default: bool,
+}
+
+/// A `trait` definition and its methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraitDef {
+    pub name: String,
+    pub span: Span,
+    pub methods: Vec<TraitMethod>,
+}
+
+/// An `impl` block, either inherent (`impl Point { .. }`) or a trait impl
+/// (`impl Animal for Dog { .. }`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImplBlock {
+    /// The type the impl targets, e.g. `Dog`, `Container`, `Wrapper`.
+    pub target: String,
+    /// Raw generic parameter list between `impl` and the target/trait,
+    /// e.g. `<T>` or `<'a, T>`; empty if the impl isn't generic.
+    pub generics: String,
+    /// `Some(trait_name)` for a trait impl, `None` for an inherent impl.
+    pub trait_name: Option<String>,
+    pub span: Span,
+    /// Names of the methods given an explicit body in this impl block.
+    pub methods: Vec<String>,
+}
+
+/// Where a trait impl's method body ultimately comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodOrigin {
+    /// Given an explicit body in the `impl` block.
+    Explicit,
+    /// Left unimplemented, falling back to the trait's default body.
+    Default,
+}
+
+/// Everything this module extracts from a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImplGraph {
+    pub traits: Vec<TraitDef>,
+    pub impls: Vec<ImplBlock>,
+}
+
+impl ImplGraph {
+    /// The names of every trait `type_name` implements.
+    pub fn traits_of(&self, type_name: &str) -> Vec<&str> {
+        self.impls
+            .iter()
+            .filter(|i| i.target == type_name)
+            .filter_map(|i| i.trait_name.as_deref())
+            .collect()
+    }
+
+    /// For `type_name`'s impl of `trait_name`, every trait method paired
+    /// with whether it came from an explicit body in the impl or is left to
+    /// the trait's default. `None` if no such impl (or trait) is known.
+    pub fn method_origins(&self, type_name: &str, trait_name: &str) -> Option<Vec<(String, MethodOrigin)>> {
+        let impl_block = self
+            .impls
+            .iter()
+            .find(|i| i.target == type_name && i.trait_name.as_deref() == Some(trait_name))?;
+        let trait_def = self.traits.iter().find(|t| t.name == trait_name)?;
+
+        Some(
+            trait_def
+                .methods
+                .iter()
+                .map(|m| {
+                    let origin = if impl_block.methods.iter().any(|n| n == &m.name) {
+                        MethodOrigin::Explicit
+                    } else {
+                        MethodOrigin::Default
+                    };
+                    (m.name.clone(), origin)
+                })
+                .collect(),
+        )
+    }
+}
+
+enum Frame {
+    Trait(TraitDef),
+    Impl(ImplBlock),
+    Other,
+}
+
+/// Extracts the trait/impl linkage graph from `source`.
+pub fn extract(source: &str) -> ImplGraph {
+    let mut traits: Vec<TraitDef> = Vec::new();
+    let mut impls: Vec<ImplBlock> = Vec::new();
+
+    let mut depth: usize = 0;
+    let mut stack: Vec<(Frame, usize)> = Vec::new();
+    let mut pending_open: Option<Frame> = None;
+
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let masked = mask_line(raw_line);
+        let masked_trimmed = masked.trim();
+
+        if let Some((kind, name)) = is_item_start(masked_trimmed) {
+            match kind {
+                ItemKind::Trait => {
+                    pending_open = Some(Frame::Trait(TraitDef {
+                        name,
+                        span: Span::single(line_no),
+                        methods: Vec::new(),
+                    }));
+                }
+                ItemKind::Impl => {
+                    // `is_item_start` doesn't truncate the `impl` case at its
+                    // opening brace (unlike struct/enum/fn/mod), so strip it
+                    // here before splitting out the generics and target.
+                    let name = name.trim_end_matches('{').trim();
+                    let (target, generics, trait_name) = parse_impl_target(name);
+                    pending_open = Some(Frame::Impl(ImplBlock {
+                        target,
+                        generics,
+                        trait_name,
+                        span: Span::single(line_no),
+                        methods: Vec::new(),
+                    }));
+                }
+                ItemKind::Fn => {
+                    if let Some((frame, _)) = stack.last_mut() {
+                        match frame {
+                            Frame::Trait(t) => t.methods.push(TraitMethod {
+                                name,
+                                span: Span::single(line_no),
+                                has_default: masked_trimmed.ends_with('{'),
+                            }),
+                            Frame::Impl(i) => i.methods.push(name),
+                            Frame::Other => {}
+                        }
+                    }
+                    pending_open = Some(Frame::Other);
+                }
+                _ => {
+                    pending_open = Some(Frame::Other);
+                }
+            }
+        }
+
+        for ch in masked.chars() {
+            match ch {
+                '{' => {
+                    if let Some(frame) = pending_open.take() {
+                        stack.push((frame, depth));
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if let Some((_, depth_at_open)) = stack.last() {
+                        if *depth_at_open == depth {
+                            match stack.pop().unwrap().0 {
+                                Frame::Trait(t) => traits.push(t),
+                                Frame::Impl(i) => impls.push(i),
+                                Frame::Other => {}
+                            }
+                        }
+                    }
+                }
+                ';' => pending_open = None,
+                _ => {}
+            }
+        }
+    }
+
+    ImplGraph { traits, impls }
+}
+
+/// Splits an `impl` target's raw text (everything between `impl` and `{`,
+/// minus any `where` clause) into its target type, generic parameter list,
+/// and — for a trait impl — the trait name.
+fn parse_impl_target(name: &str) -> (String, String, Option<String>) {
+    let (generics, rest) = split_generics(name.trim());
+    let rest = strip_where_clause(rest.trim());
+    match rest.find(" for ") {
+        Some(pos) => (
+            simple_type_name(&rest[pos + 5..]),
+            generics,
+            Some(simple_type_name(&rest[..pos])),
+        ),
+        None => (simple_type_name(rest), generics, None),
+    }
+}
+
+/// Strips a leading `<...>` generic parameter list (tracking nested angle
+/// brackets so bounds like `<T: Iterator<Item = U>>` aren't cut short),
+/// returning it alongside the remaining text.
+fn split_generics(name: &str) -> (String, &str) {
+    let Some(rest) = name.strip_prefix('<') else {
+        return (String::new(), name);
+    };
+    let mut depth = 1i32;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (format!("<{}", &rest[..=i]), rest[i + 1..].trim_start());
+                }
+            }
+            _ => {}
+        }
+    }
+    (String::new(), name)
+}
+
+/// Strips a trailing `where ...` clause from an impl's target text, so a
+/// same-line clause like `impl Trait for Foo where T: Clone {` doesn't get
+/// folded into the target name.
+fn strip_where_clause(rest: &str) -> &str {
+    match rest.find(" where ") {
+        Some(pos) => rest[..pos].trim(),
+        None => rest,
+    }
+}
+
+/// Reduces an impl target or trait reference like `"Container<T>"` to its
+/// plain name (`"Container"`).
+fn simple_type_name(text: &str) -> String {
+    let text = text.trim();
+    let end = text.find('<').unwrap_or(text.len());
+    text[..end].trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/classes_structs.rs");
+
+    #[test]
+    fn links_trait_impls_to_their_target() {
+        let graph = extract(FIXTURE);
+        let mut dog_traits = graph.traits_of("Dog");
+        dog_traits.sort();
+        assert_eq!(dog_traits, vec!["Animal", "Greet"]);
+    }
+
+    #[test]
+    fn distinguishes_explicit_methods_from_defaults() {
+        let graph = extract(FIXTURE);
+        let origins = graph.method_origins("Dog", "Greet").unwrap();
+        assert!(origins.contains(&("name".to_string(), MethodOrigin::Explicit)));
+        assert!(origins.contains(&("greet".to_string(), MethodOrigin::Default)));
+    }
+
+    #[test]
+    fn override_of_a_default_method_is_explicit() {
+        let graph = extract(FIXTURE);
+        let article = graph.method_origins("Article", "Summary").unwrap();
+        assert!(article.contains(&("summarize".to_string(), MethodOrigin::Explicit)));
+
+        let tweet = graph.method_origins("Tweet", "Summary").unwrap();
+        assert!(tweet.contains(&("summarize".to_string(), MethodOrigin::Default)));
+    }
+
+    #[test]
+    fn carries_generic_params_through_inherent_impls() {
+        let graph = extract(FIXTURE);
+        let container_impl = graph.impls.iter().find(|i| i.target == "Container").unwrap();
+        assert_eq!(container_impl.generics, "<T>");
+        assert!(container_impl.trait_name.is_none());
+        assert!(container_impl.methods.contains(&"get".to_string()));
+    }
+
+    #[test]
+    fn carries_lifetime_and_bounded_generics_through_where_clause_impls() {
+        let graph = extract(FIXTURE);
+        let wrapper_impl = graph.impls.iter().find(|i| i.target == "Wrapper").unwrap();
+        assert_eq!(wrapper_impl.generics, "<'a, T>");
+        assert!(wrapper_impl.methods.contains(&"describe".to_string()));
+    }
+
+    #[test]
+    fn strips_same_line_where_clause_from_a_non_generic_target() {
+        let graph = extract(FIXTURE);
+        let plain_impl = graph.impls.iter().find(|i| i.target == "Plain").unwrap();
+        assert_eq!(plain_impl.trait_name.as_deref(), Some("Named"));
+        assert!(graph.traits_of("Plain").contains(&"Named"));
+    }
+}
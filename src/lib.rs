@@ -0,0 +1,12 @@
+//! `codebuddy` parses Rust source files into symbols that downstream
+//! tooling can query: documented items and doctests, macro definitions and
+//! invocations, module/visibility structure, impl/trait linkage, closures,
+//! and the test/bench surface of a crate.
+
+pub mod closures;
+pub mod doc;
+pub mod impls;
+pub mod macros;
+pub mod modules;
+pub mod test_inventory;
+mod util;
@@ -0,0 +1,387 @@
+//! Macro and attribute parsing.
+//!
+//! Records `macro_rules!` definitions with their match arms, captures
+//! attribute invocations (`#[derive(...)]`, `#[cfg(test)]`, `#[test]`, ...)
+//! as structured metadata on the item they decorate, and records
+//! function-like macro call sites (`println!`, `format!`, `vec!`, and any
+//! user-defined macro) with their call spans.
+
+use crate::util::{flatten, is_item_start, mask_line, Span};
+
+/// A single `pattern => template` arm of a `macro_rules!` definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroArm {
+    /// Raw matcher text between the arm's outer delimiters, e.g. `$x:expr`.
+    pub pattern: String,
+    pub span: Span,
+}
+
+/// A `macro_rules!` definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroDef {
+    pub name: String,
+    pub span: Span,
+    pub arms: Vec<MacroArm>,
+}
+
+/// A single `#[...]` attribute attached to an item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    /// Raw attribute text, including the `#[` `]` delimiters.
+    pub raw: String,
+    pub span: Span,
+}
+
+impl Attribute {
+    /// The attribute's path, e.g. `derive`, `cfg`, `test` — the identifier
+    /// right after `#[`, before any `(...)` arguments.
+    pub fn path(&self) -> &str {
+        let inner = self
+            .raw
+            .trim_start_matches("#[")
+            .trim_end_matches(']');
+        let end = inner.find(['(', ' ']).unwrap_or(inner.len());
+        inner[..end].trim()
+    }
+
+    /// The comma-separated trait names inside `#[derive(...)]`, or an empty
+    /// list if this attribute isn't a derive.
+    pub fn derive_traits(&self) -> Vec<String> {
+        if self.path() != "derive" {
+            return Vec::new();
+        }
+        let inner = self.raw.trim_start_matches("#[").trim_end_matches(']');
+        match inner.find('(') {
+            Some(open) => inner[open + 1..]
+                .trim_end_matches(')')
+                .split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// An item decorated with one or more attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributedItem {
+    pub name: String,
+    pub kind: String,
+    pub span: Span,
+    pub attributes: Vec<Attribute>,
+}
+
+impl AttributedItem {
+    /// All trait names contributed by `#[derive(...)]` attributes on this item.
+    pub fn derives(&self) -> Vec<String> {
+        self.attributes.iter().flat_map(|a| a.derive_traits()).collect()
+    }
+}
+
+/// A function-like macro invocation, e.g. `println!("{}", x)` or `vec![1, 2]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroCall {
+    pub name: String,
+    pub span: Span,
+}
+
+/// Everything this module extracts from a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MacroInfo {
+    pub macro_defs: Vec<MacroDef>,
+    pub attributed_items: Vec<AttributedItem>,
+    pub calls: Vec<MacroCall>,
+}
+
+pub fn extract(source: &str) -> MacroInfo {
+    MacroInfo {
+        macro_defs: parse_macro_defs(source),
+        attributed_items: parse_attributed_items(source),
+        calls: parse_calls(source),
+    }
+}
+
+fn parse_attributed_items(source: &str) -> Vec<AttributedItem> {
+    let mut items = Vec::new();
+    let mut pending: Vec<Attribute> = Vec::new();
+
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+        if trimmed.starts_with("#[") && trimmed.ends_with(']') {
+            pending.push(Attribute {
+                raw: trimmed.to_string(),
+                span: Span::single(line_no),
+            });
+            continue;
+        }
+
+        let masked = mask_line(raw_line);
+        if let Some((kind, name)) = is_item_start(masked.trim()) {
+            if !pending.is_empty() {
+                items.push(AttributedItem {
+                    name,
+                    kind: kind.as_str().to_string(),
+                    span: Span::single(line_no),
+                    attributes: std::mem::take(&mut pending),
+                });
+            }
+        } else {
+            pending.clear();
+        }
+    }
+    items
+}
+
+/// Scans for `macro_rules! name { ... }` definitions and their arms.
+fn parse_macro_defs(source: &str) -> Vec<MacroDef> {
+    let flat = flatten(source);
+    let n = flat.len();
+    let mut defs = Vec::new();
+    let mut i = 0;
+    while i < n {
+        if matches_keyword(&flat, i, "macro_rules!") {
+            let def_start_line = flat[i].0;
+            let mut j = i + "macro_rules!".len();
+            while j < n && flat[j].2.is_whitespace() {
+                j += 1;
+            }
+            let name_start = j;
+            while j < n && (flat[j].2.is_alphanumeric() || flat[j].2 == '_') {
+                j += 1;
+            }
+            let name: String = flat[name_start..j].iter().map(|t| t.1).collect();
+            while j < n && flat[j].2.is_whitespace() {
+                j += 1;
+            }
+            if j < n && flat[j].2 == '{' {
+                let (end_idx, arms) = parse_macro_body(&flat, j + 1);
+                let def_end_line = flat[end_idx.min(n - 1)].0;
+                defs.push(MacroDef {
+                    name,
+                    span: Span {
+                        start_line: def_start_line,
+                        end_line: def_end_line,
+                    },
+                    arms,
+                });
+                i = end_idx + 1;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    defs
+}
+
+/// Consumes the body of a `macro_rules!` block (starting just after its
+/// opening `{`), returning the index of the closing `}` and the arms found.
+fn parse_macro_body(flat: &[(usize, char, char)], mut i: usize) -> (usize, Vec<MacroArm>) {
+    let n = flat.len();
+    let mut arms = Vec::new();
+    while i < n {
+        let m = flat[i].2;
+        if m.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        if m == '}' {
+            return (i, arms);
+        }
+        if let Some(close) = matching_delimiter(m) {
+            let matcher_start_line = flat[i].0;
+            let matcher_text_start = i + 1;
+            i = skip_balanced(flat, i, m, close);
+            let pattern: String = flat[matcher_text_start..i].iter().map(|t| t.1).collect();
+            i += 1; // past the matcher's closing delimiter
+            while i < n && flat[i].2.is_whitespace() {
+                i += 1;
+            }
+            if i + 1 < n && flat[i].2 == '=' && flat[i + 1].2 == '>' {
+                i += 2;
+            }
+            while i < n && flat[i].2.is_whitespace() {
+                i += 1;
+            }
+            if i < n {
+                if let Some(tmpl_close) = matching_delimiter(flat[i].2) {
+                    let tmpl_open = flat[i].2;
+                    i = skip_balanced(flat, i, tmpl_open, tmpl_close);
+                    let end_line = flat[i.min(n - 1)].0;
+                    arms.push(MacroArm {
+                        pattern: pattern.trim().to_string(),
+                        span: Span {
+                            start_line: matcher_start_line,
+                            end_line,
+                        },
+                    });
+                    i += 1;
+                }
+            }
+            while i < n && flat[i].2.is_whitespace() {
+                i += 1;
+            }
+            if i < n && flat[i].2 == ';' {
+                i += 1;
+            }
+            continue;
+        }
+        i += 1;
+    }
+    (n.saturating_sub(1), arms)
+}
+
+fn matching_delimiter(open: char) -> Option<char> {
+    match open {
+        '(' => Some(')'),
+        '[' => Some(']'),
+        '{' => Some('}'),
+        _ => None,
+    }
+}
+
+/// Advances from the opening delimiter at `i` to the index of its matching
+/// close, counting nested occurrences of the same delimiter pair.
+fn skip_balanced(flat: &[(usize, char, char)], mut i: usize, open: char, close: char) -> usize {
+    let n = flat.len();
+    let mut depth = 0i32;
+    while i < n {
+        let m = flat[i].2;
+        if m == open {
+            depth += 1;
+        } else if m == close {
+            depth -= 1;
+            if depth == 0 {
+                return i;
+            }
+        }
+        i += 1;
+    }
+    n.saturating_sub(1)
+}
+
+fn matches_keyword(flat: &[(usize, char, char)], i: usize, keyword: &str) -> bool {
+    if i > 0 {
+        let prev = flat[i - 1].2;
+        if prev.is_alphanumeric() || prev == '_' {
+            return false;
+        }
+    }
+    let kw: Vec<char> = keyword.chars().collect();
+    if i + kw.len() > flat.len() {
+        return false;
+    }
+    flat[i..i + kw.len()].iter().map(|t| t.2).eq(kw.iter().copied())
+}
+
+/// Scans for `ident!(...)` / `ident![...]` / `ident!{...}` call sites,
+/// including user-defined macros.
+fn parse_calls(source: &str) -> Vec<MacroCall> {
+    let flat = flatten(source);
+    let n = flat.len();
+    let mut calls = Vec::new();
+    let mut i = 0;
+    while i < n {
+        let m = flat[i].2;
+        if m.is_alphabetic() || m == '_' {
+            let start = i;
+            let mut j = i;
+            while j < n && (flat[j].2.is_alphanumeric() || flat[j].2 == '_') {
+                j += 1;
+            }
+            if j < n && flat[j].2 == '!' {
+                let after = j + 1;
+                if after < n {
+                    if let Some(close) = matching_delimiter(flat[after].2) {
+                        let name: String = flat[start..j].iter().map(|t| t.1).collect();
+                        if name != "macro_rules" {
+                            let line_no = flat[start].0;
+                            let open = flat[after].2;
+                            let end = skip_balanced(&flat, after, open, close);
+                            calls.push(MacroCall {
+                                name,
+                                span: Span {
+                                    start_line: line_no,
+                                    end_line: flat[end].0,
+                                },
+                            });
+                            i = end + 1;
+                            continue;
+                        }
+                    }
+                }
+            }
+            i = j;
+            continue;
+        }
+        i += 1;
+    }
+    calls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/macros.rs");
+
+    #[test]
+    fn parses_macro_rules_arms() {
+        let info = extract(FIXTURE);
+        let square = info.macro_defs.iter().find(|m| m.name == "square").unwrap();
+        assert_eq!(square.arms.len(), 1);
+        assert_eq!(square.arms[0].pattern, "$x:expr");
+
+        let max = info.macro_defs.iter().find(|m| m.name == "max").unwrap();
+        assert_eq!(max.arms.len(), 2);
+        assert_eq!(max.arms[0].pattern, "$a:expr");
+        assert_eq!(max.arms[1].pattern, "$a:expr, $b:expr");
+    }
+
+    #[test]
+    fn exposes_derive_list_without_impl_block() {
+        let info = extract(FIXTURE);
+        let point = info
+            .attributed_items
+            .iter()
+            .find(|i| i.name == "Point3D")
+            .unwrap();
+        assert_eq!(point.kind, "struct");
+        assert_eq!(point.derives(), vec!["Debug", "Clone", "PartialEq"]);
+    }
+
+    #[test]
+    fn records_non_derive_attributes() {
+        let info = extract(FIXTURE);
+        let extra = info.attributed_items.iter().find(|i| i.name == "Extra").unwrap();
+        assert!(extra.derives().is_empty());
+        assert_eq!(extra.attributes[0].path(), "cfg");
+    }
+
+    #[test]
+    fn records_test_module_and_fn_attributes() {
+        let info = extract(FIXTURE);
+        let tests_mod = info.attributed_items.iter().find(|i| i.name == "tests").unwrap();
+        assert_eq!(tests_mod.attributes[0].path(), "cfg");
+
+        let test_fn = info
+            .attributed_items
+            .iter()
+            .find(|i| i.name == "test_describe")
+            .unwrap();
+        assert_eq!(test_fn.attributes[0].path(), "test");
+    }
+
+    #[test]
+    fn records_macro_call_sites() {
+        let info = extract(FIXTURE);
+        let names: Vec<&str> = info.calls.iter().map(|c| c.name.as_str()).collect();
+        for expected in ["max", "square", "format", "println", "vec", "assert_eq"] {
+            assert!(names.contains(&expected), "missing call site for {expected}: {names:?}");
+        }
+    }
+}
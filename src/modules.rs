@@ -0,0 +1,404 @@
+//! Module/visibility tree construction and `use` resolution.
+//!
+//! Walks `mod` nesting (both inline `mod foo { ... }` blocks and file-backed
+//! `mod foo;` declarations) to give every item a fully-qualified path and a
+//! resolved visibility, then matches `use` imports — including glob imports
+//! like `super::*` — against those paths to record which items each import
+//! brings into scope.
+
+use crate::util::{is_item_start, mask_line, split_visibility, ItemKind, Span};
+
+/// An item's declared visibility qualifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visibility {
+    /// `pub`
+    Public,
+    /// `pub(crate)`
+    Crate,
+    /// `pub(super)`
+    Super,
+    /// `pub(in some::path)`
+    Restricted(String),
+    /// No visibility qualifier.
+    Private,
+}
+
+impl Visibility {
+    fn parse(qualifier: Option<&str>) -> Self {
+        match qualifier {
+            None => Visibility::Private,
+            Some("pub") => Visibility::Public,
+            Some("pub(crate)") => Visibility::Crate,
+            Some("pub(super)") => Visibility::Super,
+            Some(q) => {
+                let inner = q.trim_start_matches("pub(").trim_end_matches(')');
+                Visibility::Restricted(inner.trim_start_matches("in ").trim().to_string())
+            }
+        }
+    }
+}
+
+/// A single `struct`/`enum`/`trait`/`fn`/`mod`/`macro_rules!` declaration,
+/// positioned in the module tree. `impl` blocks are not recorded as items.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleItem {
+    /// Fully-qualified path from the crate root, e.g. `shapes::colors::Color`.
+    pub path: String,
+    pub kind: String,
+    pub span: Span,
+    pub visibility: Visibility,
+    /// `mod foo;` with no inline body, resolved in another file.
+    pub file_backed: bool,
+    /// Whether this item (and every module enclosing it) is `pub`, i.e.
+    /// reachable from outside the crate.
+    pub externally_visible: bool,
+}
+
+/// A `use` import, resolved against the items collected from this file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UseEdge {
+    /// The imported path with `crate`/`self`/`super` segments resolved
+    /// against the scope the `use` appears in, e.g. `shapes::colors::Color`.
+    pub path: String,
+    /// Whether this is a glob import (`use shapes::*;`).
+    pub glob: bool,
+    pub span: Span,
+    /// Fully-qualified paths of the items this import brings into scope.
+    /// Empty if nothing in this file matched (e.g. the target lives in
+    /// another crate, or in a file-backed module we don't parse here).
+    pub resolves_to: Vec<String>,
+}
+
+/// The module tree and `use` edges extracted from a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ModuleTree {
+    pub items: Vec<ModuleItem>,
+    pub uses: Vec<UseEdge>,
+}
+
+struct ScopeFrame {
+    path: String,
+    depth_at_open: usize,
+    externally_visible: bool,
+}
+
+/// Extracts the module tree and resolves `use` edges for `source`.
+pub fn extract(source: &str) -> ModuleTree {
+    let mut items: Vec<ModuleItem> = Vec::new();
+    let mut uses: Vec<UseEdge> = Vec::new();
+
+    let mut depth: usize = 0;
+    let mut scope_stack: Vec<ScopeFrame> = Vec::new();
+    let mut pending_open: Option<(String, bool)> = None;
+
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let masked = mask_line(raw_line);
+        let masked_trimmed = masked.trim();
+        let current_scope = scope_stack.last().map(|f| f.path.as_str()).unwrap_or("");
+
+        if let Some(rest) = masked_trimmed
+            .strip_prefix("use ")
+            .or_else(|| masked_trimmed.strip_prefix("pub use "))
+        {
+            uses.push(parse_use(rest, line_no, current_scope));
+        } else if let Some((kind, name)) = is_item_start(masked_trimmed) {
+            let enclosing_visible = scope_stack.last().is_none_or(|f| f.externally_visible);
+
+            if kind == ItemKind::Impl {
+                // `is_item_start` doesn't truncate the `impl` case at its
+                // opening brace or a same-line `where` clause (unlike
+                // struct/enum/fn/mod), so clean it up before qualifying
+                // nested items by it. The impl block itself isn't a module
+                // item in its own right (it has no visibility or kind that
+                // fits `ModuleItem`), so only its scope is pushed.
+                let qualified = qualify(current_scope, &impl_scope_name(&name));
+                pending_open = Some((qualified, enclosing_visible));
+                continue_scanning_braces(&masked, &mut depth, &mut scope_stack, &mut pending_open);
+                continue;
+            }
+
+            let (qualifier, _) = split_visibility(masked_trimmed);
+            let qualified = qualify(current_scope, &name);
+            let visibility = Visibility::parse(qualifier);
+            let externally_visible = enclosing_visible && matches!(visibility, Visibility::Public);
+            let kind_str = kind.as_str();
+            let file_backed = kind_str == "mod" && trimmed.ends_with(';');
+
+            items.push(ModuleItem {
+                path: qualified.clone(),
+                kind: kind_str.to_string(),
+                span: Span::single(line_no),
+                visibility,
+                file_backed,
+                externally_visible,
+            });
+
+            if matches!(kind, ItemKind::Mod | ItemKind::Trait) && !file_backed {
+                pending_open = Some((qualified, externally_visible));
+            }
+        }
+
+        continue_scanning_braces(&masked, &mut depth, &mut scope_stack, &mut pending_open);
+    }
+
+    resolve_use_targets(&mut uses, &items);
+    ModuleTree { items, uses }
+}
+
+/// Tracks brace depth, pushing `pending_open` as a new scope frame on the
+/// next `{` and popping the innermost frame once depth unwinds past where it
+/// was opened.
+fn continue_scanning_braces(
+    masked: &str,
+    depth: &mut usize,
+    scope_stack: &mut Vec<ScopeFrame>,
+    pending_open: &mut Option<(String, bool)>,
+) {
+    for ch in masked.chars() {
+        match ch {
+            '{' => {
+                if let Some((path, externally_visible)) = pending_open.take() {
+                    scope_stack.push(ScopeFrame {
+                        path,
+                        depth_at_open: *depth,
+                        externally_visible,
+                    });
+                }
+                *depth += 1;
+            }
+            '}' => {
+                *depth = depth.saturating_sub(1);
+                if let Some(top) = scope_stack.last() {
+                    if top.depth_at_open == *depth {
+                        scope_stack.pop();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn qualify(enclosing_path: &str, name: &str) -> String {
+    if enclosing_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{enclosing_path}::{name}")
+    }
+}
+
+/// Reduces an `impl` block's raw, untruncated name text (e.g. `"Circle {"`,
+/// `"<T> Container<T> {"`, `"Named for Plain where Plain: Sized {"`) to the
+/// plain target type name nested items should be qualified under.
+fn impl_scope_name(raw: &str) -> String {
+    let rest = raw.trim().trim_end_matches('{').trim();
+    let (_, rest) = split_generics(rest);
+    let rest = strip_where_clause(rest.trim());
+    let target = match rest.find(" for ") {
+        Some(pos) => &rest[pos + 5..],
+        None => rest,
+    };
+    simple_type_name(target)
+}
+
+/// Strips a leading `<...>` generic parameter list (tracking nested angle
+/// brackets), returning it alongside the remaining text. Mirrors
+/// `impls.rs`'s helper of the same name.
+fn split_generics(name: &str) -> (String, &str) {
+    let Some(rest) = name.strip_prefix('<') else {
+        return (String::new(), name);
+    };
+    let mut depth = 1i32;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (format!("<{}", &rest[..=i]), rest[i + 1..].trim_start());
+                }
+            }
+            _ => {}
+        }
+    }
+    (String::new(), name)
+}
+
+/// Strips a trailing `where ...` clause. Mirrors `impls.rs`'s helper of the
+/// same name.
+fn strip_where_clause(rest: &str) -> &str {
+    match rest.find(" where ") {
+        Some(pos) => rest[..pos].trim(),
+        None => rest,
+    }
+}
+
+/// Reduces a type reference like `"Container<T>"` to its plain name
+/// (`"Container"`). Mirrors `impls.rs`'s helper of the same name.
+fn simple_type_name(text: &str) -> String {
+    let text = text.trim();
+    let end = text.find('<').unwrap_or(text.len());
+    text[..end].trim().to_string()
+}
+
+/// Parses the text after `use`/`pub use`, resolving a leading `crate`,
+/// `self`, or `super` segment against `current_scope`.
+fn parse_use(rest: &str, line_no: usize, current_scope: &str) -> UseEdge {
+    let body = rest.trim().trim_end_matches(';').trim();
+    let glob = body.ends_with("::*") || body == "*";
+    let raw_path = body.trim_end_matches("::*").trim_end_matches('*');
+    let path = normalize_path(raw_path, current_scope);
+    UseEdge {
+        path,
+        glob,
+        span: Span::single(line_no),
+        resolves_to: Vec::new(),
+    }
+}
+
+/// Resolves a `use` path's leading `crate`/`self`/`super` segment (if any)
+/// into an absolute, crate-root-relative path.
+fn normalize_path(raw: &str, current_scope: &str) -> String {
+    let mut segments: Vec<&str> = raw.split("::").filter(|s| !s.is_empty()).collect();
+    let scope_segments = || -> Vec<&str> {
+        if current_scope.is_empty() {
+            Vec::new()
+        } else {
+            current_scope.split("::").collect()
+        }
+    };
+    match segments.first().copied() {
+        Some("crate") => {
+            segments.remove(0);
+        }
+        Some("self") => {
+            segments.remove(0);
+            let mut prefix = scope_segments();
+            prefix.extend(segments);
+            segments = prefix;
+        }
+        Some("super") => {
+            segments.remove(0);
+            let mut parent = scope_segments();
+            parent.pop();
+            parent.extend(segments);
+            segments = parent;
+        }
+        _ => {}
+    }
+    segments.join("::")
+}
+
+fn resolve_use_targets(uses: &mut [UseEdge], items: &[ModuleItem]) {
+    for use_edge in uses.iter_mut() {
+        if use_edge.glob {
+            use_edge.resolves_to = items
+                .iter()
+                .filter(|item| is_direct_child(&use_edge.path, &item.path))
+                .map(|item| item.path.clone())
+                .collect();
+        } else {
+            use_edge.resolves_to = items
+                .iter()
+                .filter(|item| item.path == use_edge.path)
+                .map(|item| item.path.clone())
+                .collect();
+        }
+    }
+}
+
+/// Whether `path` names a direct child of module `prefix` (the empty string
+/// standing for the crate root).
+fn is_direct_child(prefix: &str, path: &str) -> bool {
+    let suffix = if prefix.is_empty() {
+        path
+    } else {
+        match path.strip_prefix(prefix).and_then(|s| s.strip_prefix("::")) {
+            Some(s) => s,
+            None => return false,
+        }
+    };
+    !suffix.is_empty() && !suffix.contains("::")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/modules.rs");
+
+    fn find<'a>(items: &'a [ModuleItem], path: &str) -> &'a ModuleItem {
+        items
+            .iter()
+            .find(|i| i.path == path)
+            .unwrap_or_else(|| panic!("no item at {path}, have {:?}", items.iter().map(|i| &i.path).collect::<Vec<_>>()))
+    }
+
+    #[test]
+    fn qualifies_nested_module_items() {
+        let tree = extract(FIXTURE);
+        let color = find(&tree.items, "shapes::colors::Color");
+        assert_eq!(color.kind, "enum");
+    }
+
+    #[test]
+    fn records_visibility_kinds() {
+        let tree = extract(FIXTURE);
+        assert_eq!(find(&tree.items, "shapes::Circle").visibility, Visibility::Public);
+        assert_eq!(find(&tree.items, "shapes::Square").visibility, Visibility::Crate);
+        assert_eq!(find(&tree.items, "shapes::Internal").visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn external_visibility_requires_every_enclosing_module_to_be_pub() {
+        let tree = extract(FIXTURE);
+        assert!(find(&tree.items, "shapes::Circle").externally_visible);
+        assert!(!find(&tree.items, "shapes::Square").externally_visible);
+    }
+
+    #[test]
+    fn qualifies_methods_by_their_enclosing_impl_target() {
+        let tree = extract(FIXTURE);
+        let area = find(&tree.items, "shapes::Circle::area");
+        assert_eq!(area.kind, "fn");
+        assert!(tree.items.iter().all(|i| i.path != "shapes::area"));
+        assert!(tree.items.iter().all(|i| !i.path.contains('{')));
+    }
+
+    #[test]
+    fn detects_file_backed_module_declaration() {
+        let tree = extract(FIXTURE);
+        let helpers = find(&tree.items, "helpers");
+        assert_eq!(helpers.kind, "mod");
+        assert!(helpers.file_backed);
+    }
+
+    #[test]
+    fn resolves_named_use_import() {
+        let tree = extract(FIXTURE);
+        let circle_use = tree.uses.iter().find(|u| u.path == "shapes::Circle").unwrap();
+        assert!(!circle_use.glob);
+        assert!(circle_use.resolves_to.contains(&"shapes::Circle".to_string()));
+    }
+
+    #[test]
+    fn resolves_glob_use_import() {
+        let tree = extract(FIXTURE);
+        let glob_use = tree.uses.iter().find(|u| u.path == "shapes" && u.glob).unwrap();
+        assert!(glob_use.resolves_to.contains(&"shapes::Circle".to_string()));
+        assert!(glob_use.resolves_to.contains(&"shapes::colors".to_string()));
+    }
+
+    #[test]
+    fn resolves_super_glob_against_enclosing_scope() {
+        let tree = extract(FIXTURE);
+        let super_use = tree.uses.iter().find(|u| u.path.is_empty() && u.glob).unwrap();
+        assert!(super_use.resolves_to.contains(&"describe_circle".to_string()));
+    }
+}
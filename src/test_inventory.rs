@@ -0,0 +1,275 @@
+//! Test and benchmark inventory extraction.
+//!
+//! Walks `fn` items for `#[test]` and `#[bench]` attributes (plus
+//! `#[ignore]` and `#[should_panic]`), recording each one's name, kind,
+//! span, and enclosing module path. Modules gated by `#[cfg(test)]` are
+//! tracked separately and their contents are marked test-scoped, so
+//! tooling built on this inventory can tell a project's real test/bench
+//! surface apart from its live code without invoking cargo.
+
+use crate::util::{is_item_start, mask_line, ItemKind, Span};
+
+/// Whether a discovered item is a `#[test]` or a `#[bench]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestKind {
+    Test,
+    Bench,
+}
+
+/// The effect of a `#[should_panic]` attribute, if any.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShouldPanic {
+    /// No `#[should_panic]` attribute.
+    No,
+    /// `#[should_panic]` with no `expected = "..."`.
+    Any,
+    /// `#[should_panic(expected = "...")]`.
+    Expected(String),
+}
+
+/// A single `#[test]`/`#[bench]` function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestEntry {
+    pub name: String,
+    pub kind: TestKind,
+    pub span: Span,
+    /// Fully-qualified path of the module this item is declared in, e.g.
+    /// `tests` or `benches`.
+    pub module_path: String,
+    /// Whether `#[ignore]` is also present.
+    pub ignored: bool,
+    pub should_panic: ShouldPanic,
+    /// Whether this item lives inside a `#[cfg(test)]` module (itself or
+    /// an ancestor), and so is absent from a non-test build.
+    pub test_scoped: bool,
+}
+
+/// Everything this module extracts from a source file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TestInventory {
+    pub entries: Vec<TestEntry>,
+    /// Fully-qualified paths of modules gated by `#[cfg(test)]`.
+    pub cfg_test_modules: Vec<String>,
+}
+
+impl TestInventory {
+    /// How many entries of `kind` were found.
+    pub fn count(&self, kind: TestKind) -> usize {
+        self.entries.iter().filter(|e| e.kind == kind).count()
+    }
+}
+
+struct ModFrame {
+    path: String,
+    depth_at_open: usize,
+    test_scoped: bool,
+}
+
+/// Extracts the test/bench inventory from `source`.
+pub fn extract(source: &str) -> TestInventory {
+    let mut entries = Vec::new();
+    let mut cfg_test_modules = Vec::new();
+
+    let mut depth: usize = 0;
+    let mut scope_stack: Vec<ModFrame> = Vec::new();
+    let mut pending_mod_open: Option<(String, bool)> = None;
+    let mut pending_attrs: Vec<String> = Vec::new();
+
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() || trimmed.starts_with("//") {
+            continue;
+        }
+
+        let masked = mask_line(raw_line);
+        let masked_trimmed = masked.trim();
+        let current_path = scope_stack.last().map(|f| f.path.as_str()).unwrap_or("");
+        let inherited_test_scope = scope_stack.last().is_some_and(|f| f.test_scoped);
+
+        if trimmed.starts_with("#[") && trimmed.ends_with(']') {
+            pending_attrs.push(trimmed.to_string());
+            continue;
+        }
+
+        if let Some((kind, name)) = is_item_start(masked_trimmed) {
+            match kind {
+                ItemKind::Mod => {
+                    let gated_here = pending_attrs.iter().any(|a| is_cfg_test_attr(a));
+                    let qualified = qualify(current_path, &name);
+                    let test_scoped = inherited_test_scope || gated_here;
+                    if gated_here {
+                        cfg_test_modules.push(qualified.clone());
+                    }
+                    if !trimmed.ends_with(';') {
+                        pending_mod_open = Some((qualified, test_scoped));
+                    }
+                }
+                ItemKind::Fn => {
+                    if let Some(test_kind) = pending_attrs.iter().find_map(|a| attr_test_kind(a)) {
+                        let ignored = pending_attrs.iter().any(|a| attr_path(a) == "ignore");
+                        let should_panic = pending_attrs
+                            .iter()
+                            .find_map(|a| parse_should_panic(a))
+                            .unwrap_or(ShouldPanic::No);
+                        entries.push(TestEntry {
+                            name,
+                            kind: test_kind,
+                            span: Span::single(line_no),
+                            module_path: current_path.to_string(),
+                            ignored,
+                            should_panic,
+                            test_scoped: inherited_test_scope,
+                        });
+                    }
+                }
+                _ => {}
+            }
+            pending_attrs.clear();
+        } else if !masked_trimmed.is_empty() {
+            pending_attrs.clear();
+        }
+
+        for ch in masked.chars() {
+            match ch {
+                '{' => {
+                    if let Some((path, test_scoped)) = pending_mod_open.take() {
+                        scope_stack.push(ModFrame {
+                            path,
+                            depth_at_open: depth,
+                            test_scoped,
+                        });
+                    }
+                    depth += 1;
+                }
+                '}' => {
+                    depth = depth.saturating_sub(1);
+                    if let Some(top) = scope_stack.last() {
+                        if top.depth_at_open == depth {
+                            scope_stack.pop();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    TestInventory { entries, cfg_test_modules }
+}
+
+fn qualify(enclosing_path: &str, name: &str) -> String {
+    if enclosing_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{enclosing_path}::{name}")
+    }
+}
+
+/// The attribute's path, e.g. `test`, `cfg`, `should_panic` — the identifier
+/// right after `#[`, before any `(...)` arguments.
+fn attr_path(raw: &str) -> &str {
+    let inner = raw.trim_start_matches("#[").trim_end_matches(']');
+    let end = inner.find(['(', ' ']).unwrap_or(inner.len());
+    inner[..end].trim()
+}
+
+fn attr_test_kind(raw: &str) -> Option<TestKind> {
+    match attr_path(raw) {
+        "test" => Some(TestKind::Test),
+        "bench" => Some(TestKind::Bench),
+        _ => None,
+    }
+}
+
+/// Whether `raw` is `#[cfg(test)]` (or a `cfg` attribute whose top-level,
+/// comma-separated argument list includes a bare `test`). Predicates nested
+/// inside `all(...)`/`any(...)` are not looked into.
+fn is_cfg_test_attr(raw: &str) -> bool {
+    if attr_path(raw) != "cfg" {
+        return false;
+    }
+    let inner = raw.trim_start_matches("#[").trim_end_matches(']');
+    let args = inner.strip_prefix("cfg").unwrap_or(inner).trim();
+    let args = args.strip_prefix('(').and_then(|s| s.strip_suffix(')')).unwrap_or(args);
+    args.split(',').any(|a| a.trim() == "test")
+}
+
+fn parse_should_panic(raw: &str) -> Option<ShouldPanic> {
+    if attr_path(raw) != "should_panic" {
+        return None;
+    }
+    Some(match extract_expected(raw) {
+        Some(msg) => ShouldPanic::Expected(msg),
+        None => ShouldPanic::Any,
+    })
+}
+
+/// Extracts the quoted message from `#[should_panic(expected = "...")]`.
+fn extract_expected(raw: &str) -> Option<String> {
+    let start = raw.find('"')?;
+    let end = start + 1 + raw[start + 1..].find('"')?;
+    Some(raw[start + 1..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/test_bench.rs");
+
+    fn find<'a>(inventory: &'a TestInventory, name: &str) -> &'a TestEntry {
+        inventory
+            .entries
+            .iter()
+            .find(|e| e.name == name)
+            .unwrap_or_else(|| panic!("no entry named {name}"))
+    }
+
+    #[test]
+    fn discovers_plain_test_and_its_module_path() {
+        let inventory = extract(FIXTURE);
+        let entry = find(&inventory, "test_parse_count");
+        assert_eq!(entry.kind, TestKind::Test);
+        assert_eq!(entry.module_path, "tests");
+        assert!(!entry.ignored);
+        assert_eq!(entry.should_panic, ShouldPanic::No);
+    }
+
+    #[test]
+    fn records_ignore_attribute() {
+        let inventory = extract(FIXTURE);
+        assert!(find(&inventory, "test_parse_count_large_input").ignored);
+    }
+
+    #[test]
+    fn records_bare_should_panic() {
+        let inventory = extract(FIXTURE);
+        assert_eq!(find(&inventory, "test_checked_divide_by_zero").should_panic, ShouldPanic::Any);
+    }
+
+    #[test]
+    fn records_should_panic_with_expected_message() {
+        let inventory = extract(FIXTURE);
+        let expected = find(&inventory, "test_parse_count_invalid").should_panic.clone();
+        assert_eq!(expected, ShouldPanic::Expected("must be a valid count".to_string()));
+    }
+
+    #[test]
+    fn discovers_bench_functions_in_their_own_module() {
+        let inventory = extract(FIXTURE);
+        let entry = find(&inventory, "bench_parse_count");
+        assert_eq!(entry.kind, TestKind::Bench);
+        assert_eq!(entry.module_path, "benches");
+        assert_eq!(inventory.count(TestKind::Bench), 2);
+    }
+
+    #[test]
+    fn marks_cfg_test_modules_and_their_contents_as_test_scoped() {
+        let inventory = extract(FIXTURE);
+        let mut modules = inventory.cfg_test_modules.clone();
+        modules.sort();
+        assert_eq!(modules, vec!["benches", "tests"]);
+        assert!(find(&inventory, "test_parse_count").test_scoped);
+    }
+}
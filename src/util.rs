@@ -0,0 +1,191 @@
+//! Small line-oriented helpers shared by the parsing subsystems in this
+//! crate. `codebuddy` works a line at a time rather than building a full
+//! token stream, so these helpers exist to keep that scanning consistent
+//! (and its string/comment-masking correct) across modules.
+
+/// A span of source lines, 1-indexed and inclusive on both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl Span {
+    pub fn single(line: usize) -> Self {
+        Span {
+            start_line: line,
+            end_line: line,
+        }
+    }
+}
+
+/// The kind of item a declaration line introduces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Fn,
+    Mod,
+    MacroRules,
+}
+
+impl ItemKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ItemKind::Struct => "struct",
+            ItemKind::Enum => "enum",
+            ItemKind::Trait => "trait",
+            ItemKind::Impl => "impl",
+            ItemKind::Fn => "fn",
+            ItemKind::Mod => "mod",
+            ItemKind::MacroRules => "macro_rules!",
+        }
+    }
+}
+
+/// Replaces the contents of string/char literals and trailing `//` line
+/// comments with spaces, so brace-counting and keyword matching don't get
+/// confused by `format!("{}", x)` or `// looks like a mod foo {` text.
+///
+/// The returned line has the same number of characters as the input, so
+/// character positions in it still line up with the original source.
+pub fn mask_line(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut out = String::with_capacity(chars.len());
+    let mut in_string = false;
+    let mut in_char = false;
+    let mut escape = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if in_string {
+            if escape {
+                escape = false;
+            } else if c == '\\' {
+                escape = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            out.push(if c == '"' { '"' } else { ' ' });
+        } else if in_char {
+            if escape {
+                escape = false;
+            } else if c == '\\' {
+                escape = true;
+            } else if c == '\'' {
+                in_char = false;
+            }
+            out.push(if c == '\'' { '\'' } else { ' ' });
+        } else if c == '"' {
+            in_string = true;
+            out.push('"');
+        } else if c == '\'' && i + 1 < chars.len() && starts_char_literal(&chars, i) {
+            in_char = true;
+            out.push('\'');
+        } else if c == '/' && i + 1 < chars.len() && chars[i + 1] == '/' {
+            // Doc comments (`///`, `//!`) are handled by the doc module on
+            // the *unmasked* line; plain `//` comments are blanked here.
+            // Padded with spaces (not truncated) so character positions in
+            // the rest of the line stay aligned with the original.
+            out.extend(std::iter::repeat_n(' ', chars.len() - i));
+            break;
+        } else {
+            out.push(c);
+        }
+        i += 1;
+    }
+    out
+}
+
+/// Heuristic for whether a `'` at position `i` opens a char literal (`'a'`)
+/// rather than a lifetime (`'a`). Lifetimes are never followed by a closing
+/// quote within a couple of characters, so look for one.
+fn starts_char_literal(chars: &[char], i: usize) -> bool {
+    // `'\''`, `'a'`, `'\n'` - a closing quote right after one character, or
+    // after a backslash and one character.
+    let mut j = i + 1;
+    if j < chars.len() && chars[j] == '\\' {
+        j += 1;
+    }
+    j += 1;
+    j < chars.len() && chars[j] == '\''
+}
+
+/// Strips a single leading `pub` / `pub(crate)` / `pub(super)` / `pub(in
+/// ...)` visibility qualifier, returning the visibility text (or `None` for
+/// private) and the remainder of the line.
+pub fn split_visibility(trimmed: &str) -> (Option<&str>, &str) {
+    if let Some(rest) = trimmed.strip_prefix("pub(") {
+        if let Some(close) = rest.find(')') {
+            let qualifier = &trimmed[..close + 5];
+            return (Some(qualifier), rest[close + 1..].trim_start());
+        }
+    }
+    if let Some(rest) = trimmed.strip_prefix("pub ") {
+        return (Some("pub"), rest.trim_start());
+    }
+    if trimmed == "pub" {
+        return (Some("pub"), "");
+    }
+    (None, trimmed)
+}
+
+/// Recognizes an item-declaration line (skipping any leading visibility
+/// qualifier) and returns its kind plus the raw name text up to the first of
+/// `{`, `(`, `;`, `<`, whitespace, or end of line. For `impl`, the whole rest
+/// of the line after the keyword is returned untruncated.
+pub fn is_item_start(trimmed: &str) -> Option<(ItemKind, String)> {
+    let (_, rest) = split_visibility(trimmed);
+    let rest = rest.trim_start();
+
+    // Tuple/unit structs (`struct Color(u8);`, `struct Marker;`) are still
+    // matched by the `"struct "` case below since there's always a space
+    // before the name.
+    const KEYWORDS: &[(&str, ItemKind)] = &[
+        ("struct ", ItemKind::Struct),
+        ("enum ", ItemKind::Enum),
+        ("trait ", ItemKind::Trait),
+        ("fn ", ItemKind::Fn),
+        ("mod ", ItemKind::Mod),
+        ("macro_rules! ", ItemKind::MacroRules),
+    ];
+    for (kw, kind) in KEYWORDS {
+        if let Some(after) = rest.strip_prefix(kw) {
+            let name = take_identifier_region(after);
+            return Some((*kind, name));
+        }
+    }
+    if rest.starts_with("impl") && (rest == "impl" || rest[4..].starts_with(['<', ' '])) {
+        let after = rest[4..].trim_start();
+        return Some((ItemKind::Impl, after.to_string()));
+    }
+    None
+}
+
+/// Flattens `source` into `(line_no, raw_char, masked_char)` triples, with
+/// each line's trailing newline represented as a space in the masked stream
+/// so balanced-delimiter scans that span multiple lines (e.g. `macro_rules!`
+/// arms) can walk the whole file without losing track of line numbers.
+pub fn flatten(source: &str) -> Vec<(usize, char, char)> {
+    let mut out = Vec::new();
+    for (idx, raw_line) in source.lines().enumerate() {
+        let line_no = idx + 1;
+        let masked = mask_line(raw_line);
+        for (r, m) in raw_line.chars().zip(masked.chars()) {
+            out.push((line_no, r, m));
+        }
+        out.push((line_no, '\n', ' '));
+    }
+    out
+}
+
+/// Takes the text up to (but not including) the first `{`, `(`, `;`, `<`, or
+/// whitespace, which is the identifier for most item kinds.
+fn take_identifier_region(s: &str) -> String {
+    let end = s
+        .find(|c: char| c == '{' || c == '(' || c == ';' || c == '<' || c.is_whitespace())
+        .unwrap_or(s.len());
+    s[..end].to_string()
+}
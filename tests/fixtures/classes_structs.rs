@@ -144,6 +144,78 @@ impl Meters {
     }
 }
 
+// Trait with a default method that some implementors override explicitly
+trait Summary {
+    fn title(&self) -> &str;
+
+    fn summarize(&self) -> String {
+        format!("{} (no summary available)", self.title())
+    }
+}
+
+struct Article {
+    headline: String,
+    body: String,
+}
+
+// Overrides the default `summarize` instead of relying on it
+impl Summary for Article {
+    fn title(&self) -> &str {
+        &self.headline
+    }
+
+    fn summarize(&self) -> String {
+        format!("{}: {}", self.headline, self.body)
+    }
+}
+
+struct Tweet {
+    text: String,
+}
+
+// Relies entirely on the default `summarize` implementation
+impl Summary for Tweet {
+    fn title(&self) -> &str {
+        &self.text
+    }
+}
+
+// Generic impl combining a trait bound, a where clause, and a lifetime to
+// exercise the full generic/lifetime linkage path
+struct Wrapper<'a, T> {
+    label: &'a str,
+    value: T,
+}
+
+impl<'a, T> Wrapper<'a, T>
+where
+    T: Clone + std::fmt::Debug,
+{
+    fn new(label: &'a str, value: T) -> Self {
+        Wrapper { label, value }
+    }
+
+    fn describe(&self) -> String {
+        format!("{}: {:?}", self.label, self.value)
+    }
+}
+
+// Trait impl with a `where` clause on the same line as a target that has no
+// generics of its own, so there's no `<` for `simple_type_name` to truncate
+// at by accident; this is the case `Wrapper` above doesn't cover, since its
+// `where` clause sits on its own line and its target already has generics.
+trait Named {
+    fn label(&self) -> String;
+}
+
+struct Plain;
+
+impl Named for Plain where Plain: Sized {
+    fn label(&self) -> String {
+        "plain".to_string()
+    }
+}
+
 fn main() {
     let p1 = Point::new(0, 0);
     let p2 = Point::new(3, 4);
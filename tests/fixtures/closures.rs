@@ -0,0 +1,105 @@
+/// Rust test fixture for closures, nested functions, and capture detection.
+/// Tests non-capturing and capturing closures, `move` closures, and
+/// higher-order functions.
+
+// Closure with no captures, only a parameter
+fn increment_all(numbers: &[i32]) -> Vec<i32> {
+    numbers.iter().map(|n| n + 1).collect()
+}
+
+// Closure that captures an outer variable by reference
+fn add_offset(numbers: &[i32], offset: i32) -> Vec<i32> {
+    numbers.iter().map(|n| n + offset).collect()
+}
+
+// Closure that captures one outer parameter; its accumulator is its own
+// parameter, and the local `base` is passed in as an argument, not captured
+fn scaled_sum(numbers: &[i32], scale: i32) -> i32 {
+    let base = 10;
+    numbers.iter().fold(base, |acc, n| acc + n * scale)
+}
+
+// `move` closure that takes ownership of a captured variable
+fn make_greeter(name: String) -> impl Fn() -> String {
+    move || format!("Hello, {}!", name)
+}
+
+// Nested named function, not a closure, with no access to outer locals
+fn outer(n: i32) -> i32 {
+    fn inner(x: i32) -> i32 {
+        x * 2
+    }
+    inner(n) + 1
+}
+
+// Function that accepts another function as an argument
+fn apply_twice(f: impl Fn(i32) -> i32, x: i32) -> i32 {
+    f(f(x))
+}
+
+// Function passing closures as callbacks, both to iterator adapters and to
+// the higher-order `apply_twice` above
+fn run_pipeline(values: &[i32]) -> i32 {
+    let doubled: Vec<i32> = values.iter().map(|v| v * 2).collect();
+    let total: i32 = doubled.iter().filter(|v| **v > 0).sum();
+    apply_twice(|x| x + total, 0)
+}
+
+// Closure with a block body spanning multiple lines, capturing an outer
+// parameter inside that block rather than in a single expression
+fn scale_all(numbers: &[i32], limit: i32) -> Vec<i32> {
+    numbers
+        .iter()
+        .map(|n| {
+            let capped = n + limit;
+            capped * 2
+        })
+        .collect()
+}
+
+// Two structs with same-named constructors, each building a capturing
+// closure, to exercise qualifying a method's name (and its closures' owner)
+// by its enclosing `impl` rather than just the bare function name.
+struct Adder {
+    x: i32,
+}
+
+impl Adder {
+    fn new(x: i32) -> Self {
+        Adder { x }
+    }
+
+    fn make(&self) -> impl Fn(i32) -> i32 + '_ {
+        |n| n + self.x
+    }
+}
+
+struct Multiplier {
+    y: i32,
+}
+
+impl Multiplier {
+    fn new(y: i32) -> Self {
+        Multiplier { y }
+    }
+
+    fn make(&self) -> impl Fn(i32) -> i32 + '_ {
+        |n| n * self.y
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_offset() {
+        assert_eq!(add_offset(&[1, 2, 3], 10), vec![11, 12, 13]);
+    }
+
+    #[test]
+    fn test_make_greeter() {
+        let greeter = make_greeter(String::from("Ada"));
+        assert_eq!(greeter(), "Hello, Ada!");
+    }
+}
@@ -0,0 +1,94 @@
+//! Rust test fixture for doc-comment and doctest extraction.
+//! Tests outer (`///`) and inner (`//!`) comments, fenced doctests, and the
+//! legacy `~~~ {.rust}` fence style.
+
+// Function documented with a single doctest
+/// Adds one to the given number.
+///
+/// # Examples
+///
+/// ```rust
+/// let result = codebuddy_fixture::add_one(4);
+/// assert_eq!(result, 5);
+/// ```
+fn add_one(n: i32) -> i32 {
+    n + 1
+}
+
+// Function documented with multiple doctests
+/// Returns the square of a number.
+///
+/// ```
+/// assert_eq!(square(2), 4);
+/// ```
+///
+/// A second example showing a negative input:
+///
+/// ```rust
+/// assert_eq!(square(-3), 9);
+/// ```
+fn square(n: i32) -> i32 {
+    n * n
+}
+
+// Function documented using the legacy `~~~ {.rust}` fence style
+/// Subtracts `b` from `a`.
+///
+/// ~~~ {.rust}
+/// assert_eq!(subtract(5, 3), 2);
+/// ~~~
+fn subtract(a: i32, b: i32) -> i32 {
+    a - b
+}
+
+// Function whose doc comment contains an unterminated fence; the doctest
+// should be treated as extending to the end of the comment.
+/// Divides `a` by `b`, panicking on division by zero.
+///
+/// ```rust
+/// assert_eq!(divide(10, 2), 5);
+fn divide(a: i32, b: i32) -> i32 {
+    a / b
+}
+
+// Struct with an outer doc comment and a doctest that constructs it.
+/// A point in two-dimensional space.
+///
+/// ```rust
+/// let origin = Documented { x: 0, y: 0 };
+/// assert_eq!(origin.x, 0);
+/// ```
+struct Documented {
+    x: i32,
+    y: i32,
+}
+
+// Module documented with an inner (`//!`) comment, which is only legal as
+// the first item inside the module body (or at the top of a file).
+mod coordinates {
+    //! Coordinate helpers shared by the fixtures in this file.
+
+    pub fn origin() -> (i32, i32) {
+        (0, 0)
+    }
+}
+
+// Struct with an outer doc comment followed by a derive attribute before
+// the item itself; the doc comment must still attach to `User`.
+/// A registered user.
+#[derive(Debug, Clone)]
+struct User {
+    name: String,
+}
+
+// Trait documented with a default method that itself has a doctest.
+/// A type that can describe itself.
+trait Describe {
+    /// Returns a human-readable description.
+    ///
+    /// ```rust
+    /// let s = thing.describe();
+    /// assert!(!s.is_empty());
+    /// ```
+    fn describe(&self) -> String;
+}
@@ -0,0 +1,57 @@
+/// Rust test fixture for macro and attribute parsing.
+/// Tests `macro_rules!` definitions, attribute invocations, and macro call sites.
+
+// Simple macro_rules! definition with a single match arm
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
+
+// Macro definition with multiple match arms
+macro_rules! max {
+    ($a:expr) => {
+        $a
+    };
+    ($a:expr, $b:expr) => {
+        if $a > $b {
+            $a
+        } else {
+            $b
+        }
+    };
+}
+
+// Struct with a derive attribute and no explicit impl blocks
+#[derive(Debug, Clone, PartialEq)]
+struct Point3D {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+// Struct with a cfg attribute
+#[cfg(feature = "extra")]
+struct Extra {
+    value: i32,
+}
+
+// Function that invokes macros from std and the macros defined above
+fn describe(a: i32, b: i32) -> String {
+    let bigger = max!(a, b);
+    let squared = square!(bigger);
+    let message = format!("bigger: {}, squared: {}", bigger, squared);
+    println!("{}", message);
+    let _values = vec![a, b, bigger, squared];
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(describe(2, 3), "bigger: 3, squared: 9");
+    }
+}
@@ -0,0 +1,61 @@
+/// Rust test fixture for module nesting, visibility, and `use` resolution.
+/// Tests inline and file-backed `mod` declarations, `pub`/`pub(crate)`/private
+/// items, and glob imports.
+
+// Inline module with mixed visibility
+pub mod shapes {
+    // Publicly reachable struct
+    pub struct Circle {
+        pub radius: f64,
+    }
+
+    // Crate-visible struct
+    pub(crate) struct Square {
+        side: f64,
+    }
+
+    // Private helper, not reachable outside this module
+    struct Internal {
+        factor: f64,
+    }
+
+    impl Circle {
+        pub fn area(&self) -> f64 {
+            std::f64::consts::PI * self.radius * self.radius
+        }
+    }
+
+    // Nested module
+    pub mod colors {
+        pub enum Color {
+            Red,
+            Green,
+            Blue,
+        }
+    }
+}
+
+// File-backed module declaration (see modules/helpers.rs)
+mod helpers;
+
+// Bring specific items into scope
+use shapes::Circle;
+use shapes::colors::Color;
+
+// Glob import
+use shapes::*;
+
+fn describe_circle(c: &Circle) -> f64 {
+    c.area()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_circle() {
+        let c = Circle { radius: 2.0 };
+        assert!(describe_circle(&c) > 12.0);
+    }
+}
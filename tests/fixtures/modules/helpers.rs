@@ -0,0 +1,20 @@
+/// File-backed module used by `modules.rs` to test cross-file module resolution.
+
+pub fn double(n: i32) -> i32 {
+    n * 2
+}
+
+pub(crate) struct Counter {
+    count: u32,
+}
+
+impl Counter {
+    pub fn new() -> Self {
+        Counter { count: 0 }
+    }
+
+    pub fn increment(&mut self) -> u32 {
+        self.count += 1;
+        self.count
+    }
+}
@@ -0,0 +1,59 @@
+/// Rust test fixture for test and benchmark inventory extraction.
+/// Tests `#[test]`, `#[ignore]`, `#[should_panic]`, and `#[bench]` attributes
+/// inside a `#[cfg(test)]` module.
+
+fn parse_count(input: &str) -> u32 {
+    input.parse().expect("input must be a valid count")
+}
+
+fn checked_divide(a: i32, b: i32) -> i32 {
+    a / b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_count() {
+        assert_eq!(parse_count("42"), 42);
+    }
+
+    // Slow or environment-dependent test, skipped by default
+    #[test]
+    #[ignore]
+    fn test_parse_count_large_input() {
+        let huge = "1".repeat(1000);
+        assert!(parse_count(&huge) > 0);
+    }
+
+    // Expected to panic because the divisor is zero
+    #[test]
+    #[should_panic]
+    fn test_checked_divide_by_zero() {
+        checked_divide(10, 0);
+    }
+
+    // Expected to panic with a specific message
+    #[test]
+    #[should_panic(expected = "must be a valid count")]
+    fn test_parse_count_invalid() {
+        parse_count("not a number");
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use test::Bencher;
+
+    #[bench]
+    fn bench_parse_count(b: &mut Bencher) {
+        b.iter(|| parse_count("12345"));
+    }
+
+    #[bench]
+    fn bench_checked_divide(b: &mut Bencher) {
+        b.iter(|| checked_divide(1000, 7));
+    }
+}